@@ -0,0 +1,384 @@
+//! REST admin API mirroring the nexus JSON-RPC methods.
+//!
+//! Everything here is a thin translation layer: each route decodes its
+//! path/body into the same request type `register_rpc_methods` already
+//! accepts, calls the matching JSON-RPC method against the mayastor
+//! backend, and turns the reply (or `Error`) into a JSON HTTP response.
+//! Operators and debugging scripts that can't easily speak JSON-RPC-over-
+//! SPDK or the protobuf gRPC surface can `curl` this instead.
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+use serde_json::json;
+
+use rpc::mayastor::{
+    AddChildNexusRequest,
+    ChildNexusRequest,
+    CreateNexusRequest,
+    DestroyNexusRequest,
+    PublishNexusRequest,
+    RebuildProgressRequest,
+    RebuildStateRequest,
+    RemoveChildNexusRequest,
+    StartRebuildRequest,
+    StopRebuildRequest,
+    UnpublishNexusRequest,
+};
+
+use crate::{jsonrpc_client, supervisor::Supervisor};
+
+/// Percent-decode a single path segment (child URIs contain `/` and `?`).
+fn decode_segment(segment: &str) -> String {
+    percent_decode(segment.as_bytes())
+}
+
+fn percent_decode(bytes: &[u8]) -> String {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    while let Some(&b) = iter.next() {
+        if b == b'%' {
+            let hi = iter.next().copied();
+            let lo = iter.next().copied();
+            match (hi.and_then(hex_val), lo.and_then(hex_val)) {
+                (Some(hi), Some(lo)) => out.push(hi << 4 | lo),
+                _ => out.push(b'%'),
+            }
+        } else {
+            out.push(b);
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex_val(c: u8) -> Option<u8> {
+    match c {
+        b'0' ..= b'9' => Some(c - b'0'),
+        b'a' ..= b'f' => Some(c - b'a' + 10),
+        b'A' ..= b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Map a JSON-RPC error (as rendered by `nexus_rpc::Error`) onto an HTTP
+/// status code. This is string matching rather than a typed decode because
+/// the JSON-RPC wire error is just the `Display` of the error - good enough
+/// for an admin/debugging surface.
+///
+/// The wire text is human-readable prose, not the Rust variant name (e.g.
+/// "nexus with uuid ... not found", not "NexusNotFound"), so match on the
+/// common English words such a message is likely to contain rather than
+/// PascalCase identifiers that will never appear in it.
+fn status_for_error(message: &str) -> StatusCode {
+    let message = message.to_lowercase();
+    if message.contains("not found") {
+        StatusCode::NOT_FOUND
+    } else if message.contains("already") || message.contains("exists") {
+        StatusCode::CONFLICT
+    } else if message.contains("invalid") {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+fn json_response<T: serde::Serialize>(
+    status: StatusCode,
+    body: &T,
+) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .unwrap()
+}
+
+fn error_response(err: jsonrpc_client::Error) -> Response<Body> {
+    let message = err.to_string();
+    let status = match &err {
+        jsonrpc_client::Error::Rpc(msg) => status_for_error(msg),
+        _ => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    json_response(status, &json!({ "error": message }))
+}
+
+async fn read_body<T: serde::de::DeserializeOwned>(
+    req: Request<Body>,
+) -> Result<T, Response<Body>> {
+    let bytes = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            return Err(json_response(
+                StatusCode::BAD_REQUEST,
+                &json!({ "error": err.to_string() }),
+            ))
+        }
+    };
+    serde_json::from_slice(&bytes).map_err(|err| {
+        json_response(
+            StatusCode::BAD_REQUEST,
+            &json!({ "error": format!("invalid request body: {}", err) }),
+        )
+    })
+}
+
+async fn route(
+    req: Request<Body>,
+    supervisor: Arc<Supervisor>,
+) -> Result<Response<Body>, Infallible> {
+    if let Err(err) = supervisor.ensure_connected() {
+        return Ok(json_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &json!({ "error": err.to_string() }),
+        ));
+    }
+    let socket = supervisor.socket();
+
+    let method = req.method().clone();
+    let segments: Vec<String> = req
+        .uri()
+        .path()
+        .trim_matches('/')
+        .split('/')
+        .map(decode_segment)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let response = match (&method, segments.as_slice()) {
+        (&Method::GET, ["v0", "nexuses"]) => {
+            jsonrpc_client::call::<_, serde_json::Value>(
+                socket,
+                "list_nexus",
+                (),
+            )
+            .await
+            .map(|reply| json_response(StatusCode::OK, &reply))
+        }
+        (&Method::POST, ["v0", "nexuses"]) => {
+            match read_body::<CreateNexusRequest>(req).await {
+                Ok(args) => jsonrpc_client::call::<_, serde_json::Value>(
+                    socket,
+                    "create_nexus",
+                    args,
+                )
+                .await
+                .map(|reply| json_response(StatusCode::CREATED, &reply)),
+                Err(resp) => return Ok(resp),
+            }
+        }
+        (&Method::DELETE, ["v0", "nexuses", uuid]) => {
+            jsonrpc_client::call::<_, ()>(
+                socket,
+                "destroy_nexus",
+                DestroyNexusRequest {
+                    uuid: uuid.to_string(),
+                },
+            )
+            .await
+            .map(|_| json_response(StatusCode::NO_CONTENT, &json!({})))
+        }
+        (&Method::PUT, ["v0", "nexuses", uuid, "share"]) => {
+            match read_body::<PublishNexusRequest>(req).await {
+                Ok(mut args) => {
+                    args.uuid = (*uuid).to_string();
+                    jsonrpc_client::call::<_, serde_json::Value>(
+                        socket,
+                        "publish_nexus",
+                        args,
+                    )
+                    .await
+                    .map(|reply| json_response(StatusCode::OK, &reply))
+                }
+                Err(resp) => return Ok(resp),
+            }
+        }
+        (&Method::DELETE, ["v0", "nexuses", uuid, "share"]) => {
+            jsonrpc_client::call::<_, ()>(
+                socket,
+                "unpublish_nexus",
+                UnpublishNexusRequest {
+                    uuid: uuid.to_string(),
+                },
+            )
+            .await
+            .map(|_| json_response(StatusCode::NO_CONTENT, &json!({})))
+        }
+        (&Method::POST, ["v0", "nexuses", uuid, "children"]) => {
+            match read_body::<AddChildNexusRequest>(req).await {
+                Ok(mut args) => {
+                    args.uuid = (*uuid).to_string();
+                    jsonrpc_client::call::<_, serde_json::Value>(
+                        socket,
+                        "add_child_nexus",
+                        args,
+                    )
+                    .await
+                    .map(|reply| json_response(StatusCode::CREATED, &reply))
+                }
+                Err(resp) => return Ok(resp),
+            }
+        }
+        (&Method::DELETE, ["v0", "nexuses", uuid, "children", child]) => {
+            jsonrpc_client::call::<_, ()>(
+                socket,
+                "remove_child_nexus",
+                RemoveChildNexusRequest {
+                    uuid: uuid.to_string(),
+                    uri: child.to_string(),
+                },
+            )
+            .await
+            .map(|_| json_response(StatusCode::NO_CONTENT, &json!({})))
+        }
+        (
+            &Method::PUT,
+            ["v0", "nexuses", uuid, "children", child, "offline"],
+        ) => jsonrpc_client::call::<_, ()>(
+            socket,
+            "offline_child",
+            ChildNexusRequest {
+                uuid: uuid.to_string(),
+                uri: child.to_string(),
+            },
+        )
+        .await
+        .map(|_| json_response(StatusCode::OK, &json!({}))),
+        (
+            &Method::PUT,
+            ["v0", "nexuses", uuid, "children", child, "online"],
+        ) => jsonrpc_client::call::<_, ()>(
+            socket,
+            "online_child",
+            ChildNexusRequest {
+                uuid: uuid.to_string(),
+                uri: child.to_string(),
+            },
+        )
+        .await
+        .map(|_| json_response(StatusCode::OK, &json!({}))),
+        (&Method::POST, ["v0", "nexuses", uuid, "rebuilds", child]) => {
+            jsonrpc_client::call::<_, serde_json::Value>(
+                socket,
+                "start_rebuild",
+                StartRebuildRequest {
+                    uuid: uuid.to_string(),
+                    uri: child.to_string(),
+                },
+            )
+            .await
+            .map(|reply| json_response(StatusCode::CREATED, &reply))
+        }
+        (&Method::DELETE, ["v0", "nexuses", uuid, "rebuilds", child]) => {
+            jsonrpc_client::call::<_, ()>(
+                socket,
+                "stop_rebuild",
+                StopRebuildRequest {
+                    uuid: uuid.to_string(),
+                    uri: child.to_string(),
+                },
+            )
+            .await
+            .map(|_| json_response(StatusCode::NO_CONTENT, &json!({})))
+        }
+        (
+            &Method::GET,
+            ["v0", "nexuses", uuid, "rebuilds", child, "state"],
+        ) => jsonrpc_client::call::<_, serde_json::Value>(
+            socket,
+            "get_rebuild_state",
+            RebuildStateRequest {
+                uuid: uuid.to_string(),
+                uri: child.to_string(),
+            },
+        )
+        .await
+        .map(|reply| json_response(StatusCode::OK, &reply)),
+        (
+            &Method::GET,
+            ["v0", "nexuses", uuid, "rebuilds", _child, "progress"],
+        ) => jsonrpc_client::call::<_, serde_json::Value>(
+            socket,
+            "get_rebuild_progress",
+            RebuildProgressRequest {
+                uuid: uuid.to_string(),
+            },
+        )
+        .await
+        .map(|reply| json_response(StatusCode::OK, &reply)),
+        _ => {
+            return Ok(json_response(
+                StatusCode::NOT_FOUND,
+                &json!({ "error": "no such admin route" }),
+            ))
+        }
+    };
+
+    Ok(response.unwrap_or_else(error_response))
+}
+
+/// Serve the REST admin API on `addr` until the process exits, forwarding
+/// every request to the mayastor backend through `supervisor`, failing fast
+/// with a 503 while it reports the backend as disconnected.
+pub async fn serve(addr: SocketAddr, supervisor: Arc<Supervisor>) {
+    let make_svc = make_service_fn(move |_conn| {
+        let supervisor = supervisor.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                route(req, supervisor.clone())
+            }))
+        }
+    });
+
+    info!("Admin API listening on {}", addr);
+    if let Err(err) = Server::bind(&addr).serve(make_svc).await {
+        error!("Admin API failed: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_human_readable_messages_to_status_codes() {
+        assert_eq!(
+            status_for_error("nexus with uuid abc-123 not found"),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status_for_error("invalid uuid specified for nexus"),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status_for_error("share protocol is invalid"),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status_for_error("child already shared"),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            status_for_error("bdev already exists"),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            status_for_error("failed to open bdev: no such device"),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn percent_decode_handles_reserved_and_invalid_sequences() {
+        assert_eq!(decode_segment("nvmf%3A%2F%2Fhost%2Fnqn"), "nvmf://host/nqn");
+        assert_eq!(decode_segment("plain"), "plain");
+        assert_eq!(decode_segment("100%"), "100%");
+    }
+}
@@ -0,0 +1,35 @@
+//! Wires the `grpc.health.v1.Health` service up to the [`Supervisor`]'s
+//! connection state, so that Kubernetes readiness/liveness probes against
+//! the agent reflect whether the mayastor backend is actually reachable
+//! rather than just whether this process is still running.
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::time::delay_for;
+use tonic::transport::NamedService;
+use tonic_health::server::HealthReporter;
+
+use crate::supervisor::Supervisor;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Continuously mirror `supervisor`'s connection state into `reporter` for
+/// service `S` until the process exits.
+pub async fn watch<S: NamedService>(
+    supervisor: Arc<Supervisor>,
+    mut reporter: HealthReporter,
+) {
+    let mut last_connected = None;
+    loop {
+        let connected = supervisor.is_connected();
+        if last_connected != Some(connected) {
+            if connected {
+                reporter.set_serving::<S>().await;
+            } else {
+                reporter.set_not_serving::<S>().await;
+            }
+            last_connected = Some(connected);
+        }
+        delay_for(POLL_INTERVAL).await;
+    }
+}
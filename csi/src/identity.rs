@@ -0,0 +1,64 @@
+//! CSI `Identity` service implementation.
+//!
+//! Deliberately thin: these three RPCs just tell the CO (container
+//! orchestrator) what plugin this is and whether it's ready to serve
+//! requests. `Probe` doubles as a liveness check against the supervised
+//! mayastor connection, so a CO that calls it before sending real work gets
+//! an honest answer instead of a hang on the first real RPC.
+
+use std::sync::Arc;
+
+use git_version::git_version;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    csi::{
+        identity_server::Identity as IdentityTrait,
+        GetPluginCapabilitiesRequest,
+        GetPluginCapabilitiesResponse,
+        GetPluginInfoRequest,
+        GetPluginInfoResponse,
+        ProbeRequest,
+        ProbeResponse,
+    },
+    supervisor::Supervisor,
+};
+
+const PLUGIN_NAME: &str = "io.openebs.csi-mayastor";
+
+#[derive(Debug)]
+pub struct Identity {
+    pub supervisor: Arc<Supervisor>,
+}
+
+#[tonic::async_trait]
+impl IdentityTrait for Identity {
+    async fn get_plugin_info(
+        &self,
+        _request: Request<GetPluginInfoRequest>,
+    ) -> Result<Response<GetPluginInfoResponse>, Status> {
+        Ok(Response::new(GetPluginInfoResponse {
+            name: PLUGIN_NAME.to_string(),
+            vendor_version: git_version!().to_string(),
+            manifest: Default::default(),
+        }))
+    }
+
+    async fn get_plugin_capabilities(
+        &self,
+        _request: Request<GetPluginCapabilitiesRequest>,
+    ) -> Result<Response<GetPluginCapabilitiesResponse>, Status> {
+        Ok(Response::new(GetPluginCapabilitiesResponse {
+            capabilities: vec![],
+        }))
+    }
+
+    async fn probe(
+        &self,
+        _request: Request<ProbeRequest>,
+    ) -> Result<Response<ProbeResponse>, Status> {
+        Ok(Response::new(ProbeResponse {
+            ready: Some(self.supervisor.is_connected()),
+        }))
+    }
+}
@@ -0,0 +1,98 @@
+//! Minimal line-delimited JSON-RPC client used to talk to the mayastor
+//! backend over its Unix domain socket.
+//!
+//! This speaks the same protocol as mayastor's `jsonrpc_register` handlers
+//! (see `mayastor::bdev::nexus::nexus_rpc::register_rpc_methods`): one JSON
+//! object per line in, one JSON object per line out. It intentionally does
+//! not pool or keep connections alive - every call dials the socket fresh,
+//! which is good enough for the low request rates of the proxy and the
+//! metrics/admin endpoints built on top of it.
+
+use std::{fmt, path::Path, time::Duration};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+    time::timeout,
+};
+
+/// How long a single JSON-RPC call is allowed to take end to end (connect +
+/// write + read) before it is abandoned. A backend that accepts the
+/// connection but never replies is just as broken as one that refuses it,
+/// and without this bound a wedged backend would hang every caller forever.
+const CALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum Error {
+    Connect(std::io::Error),
+    Io(std::io::Error),
+    /// The backend replied with a JSON-RPC error object.
+    Rpc(String),
+    Decode(serde_json::Error),
+    /// The call did not complete within [`CALL_TIMEOUT`].
+    Timeout,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Connect(err) => write!(f, "failed to connect: {}", err),
+            Error::Io(err) => write!(f, "io error: {}", err),
+            Error::Rpc(msg) => write!(f, "mayastor returned an error: {}", msg),
+            Error::Decode(err) => write!(f, "failed to decode reply: {}", err),
+            Error::Timeout => {
+                write!(f, "timed out waiting for mayastor to reply")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Issue a single JSON-RPC call against the mayastor backend listening on
+/// `socket` and decode the `result` field of the reply as `R`. Bounded by
+/// [`CALL_TIMEOUT`] so a backend that accepts the connection but never
+/// responds can't hang the caller.
+pub async fn call<P, R>(socket: &Path, method: &str, params: P) -> Result<R, Error>
+where
+    P: Serialize,
+    R: DeserializeOwned,
+{
+    timeout(CALL_TIMEOUT, call_inner(socket, method, params))
+        .await
+        .unwrap_or(Err(Error::Timeout))
+}
+
+async fn call_inner<P, R>(
+    socket: &Path,
+    method: &str,
+    params: P,
+) -> Result<R, Error>
+where
+    P: Serialize,
+    R: DeserializeOwned,
+{
+    let stream = UnixStream::connect(socket).await.map_err(Error::Connect)?;
+    let (read_half, mut write_half) = tokio::io::split(stream);
+
+    let request = json!({
+        "id": 0,
+        "method": method,
+        "params": params,
+    });
+    let mut line = serde_json::to_vec(&request).map_err(Error::Decode)?;
+    line.push(b'\n');
+    write_half.write_all(&line).await.map_err(Error::Io)?;
+
+    let mut reader = BufReader::new(read_half);
+    let mut response = String::new();
+    reader.read_line(&mut response).await.map_err(Error::Io)?;
+
+    let value: Value = serde_json::from_str(&response).map_err(Error::Decode)?;
+    if let Some(err) = value.get("error") {
+        return Err(Error::Rpc(err.to_string()));
+    }
+    serde_json::from_value(value["result"].clone()).map_err(Error::Decode)
+}
@@ -0,0 +1,186 @@
+//! `Mayastor` gRPC service implementation.
+//!
+//! This is the egress side of the proxy: every method here is a 1:1
+//! forwarding of a `Mayastor` gRPC call onto the matching JSON-RPC method
+//! already registered by `mayastor::bdev::nexus::nexus_rpc::register_rpc_methods`,
+//! dialed through the shared [`Supervisor`] so a dead backend fails fast with
+//! `Status::unavailable` instead of hanging the caller.
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use rpc::{
+    mayastor::{
+        AddChildNexusRequest,
+        ChildNexusRequest,
+        CreateNexusRequest,
+        DestroyNexusRequest,
+        ListNexusReply,
+        Nexus as RpcNexus,
+        Null,
+        PublishNexusReply,
+        PublishNexusRequest,
+        RebuildProgressReply,
+        RebuildProgressRequest,
+        RebuildStateReply,
+        RebuildStateRequest,
+        RemoveChildNexusRequest,
+        StartRebuildRequest,
+        StopRebuildRequest,
+        UnpublishNexusRequest,
+    },
+    service::mayastor_server::Mayastor,
+};
+
+use crate::{jsonrpc_client, supervisor::Supervisor};
+
+#[derive(Debug)]
+pub struct MayastorService {
+    pub supervisor: Arc<Supervisor>,
+}
+
+impl MayastorService {
+    /// Fail fast with `Status::unavailable` rather than dialing a backend the
+    /// supervisor already knows is down.
+    fn ensure_connected(&self) -> Result<(), Status> {
+        self.supervisor
+            .ensure_connected()
+            .map_err(|err| Status::unavailable(err.to_string()))
+    }
+
+    async fn call<P, R>(&self, method: &str, params: P) -> Result<R, Status>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.ensure_connected()?;
+        jsonrpc_client::call(self.supervisor.socket(), method, params)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))
+    }
+}
+
+#[tonic::async_trait]
+impl Mayastor for MayastorService {
+    async fn list_nexus(
+        &self,
+        _request: Request<Null>,
+    ) -> Result<Response<ListNexusReply>, Status> {
+        self.call("list_nexus", ()).await.map(Response::new)
+    }
+
+    async fn create_nexus(
+        &self,
+        request: Request<CreateNexusRequest>,
+    ) -> Result<Response<RpcNexus>, Status> {
+        self.call("create_nexus", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn destroy_nexus(
+        &self,
+        request: Request<DestroyNexusRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("destroy_nexus", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn publish_nexus(
+        &self,
+        request: Request<PublishNexusRequest>,
+    ) -> Result<Response<PublishNexusReply>, Status> {
+        self.call("publish_nexus", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn unpublish_nexus(
+        &self,
+        request: Request<UnpublishNexusRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("unpublish_nexus", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn offline_child(
+        &self,
+        request: Request<ChildNexusRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("offline_child", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn online_child(
+        &self,
+        request: Request<ChildNexusRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("online_child", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn add_child_nexus(
+        &self,
+        request: Request<AddChildNexusRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("add_child_nexus", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn remove_child_nexus(
+        &self,
+        request: Request<RemoveChildNexusRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("remove_child_nexus", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn start_rebuild(
+        &self,
+        request: Request<StartRebuildRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("start_rebuild", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn stop_rebuild(
+        &self,
+        request: Request<StopRebuildRequest>,
+    ) -> Result<Response<Null>, Status> {
+        self.call("stop_rebuild", request.into_inner())
+            .await
+            .map(Response::new)
+    }
+
+    async fn get_rebuild_state(
+        &self,
+        request: Request<RebuildStateRequest>,
+    ) -> Result<Response<RebuildStateReply>, Status> {
+        let state = self
+            .call("get_rebuild_state", request.into_inner())
+            .await?;
+        Ok(Response::new(RebuildStateReply {
+            state,
+        }))
+    }
+
+    async fn get_rebuild_progress(
+        &self,
+        request: Request<RebuildProgressRequest>,
+    ) -> Result<Response<RebuildProgressReply>, Status> {
+        let progress = self
+            .call("get_rebuild_progress", request.into_inner())
+            .await?;
+        Ok(Response::new(RebuildProgressReply {
+            progress,
+        }))
+    }
+}
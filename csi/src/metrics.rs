@@ -0,0 +1,225 @@
+//! Prometheus `/metrics` exporter for the agent.
+//!
+//! The exporter is deliberately tiny: on every scrape it asks the mayastor
+//! backend (over the same JSON-RPC socket the gRPC proxy uses) for the
+//! current nexus and rebuild state and renders it straight into Prometheus
+//! text exposition format. There is no persistent registry and no
+//! background collection loop - state is only ever as stale as the last
+//! scrape.
+
+use std::{convert::Infallible, fmt::Write as _, net::SocketAddr, path::PathBuf};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body,
+    Method,
+    Request,
+    Response,
+    Server,
+    StatusCode,
+};
+
+use rpc::mayastor::{ListNexusReply, RebuildProgressRequest};
+
+use crate::jsonrpc_client;
+
+/// Map a nexus state string (as returned by `list_nexus`) onto the integer
+/// encoding of the `NexusState` enum so it can be used as a gauge value.
+/// Unknown strings map to `-1` rather than panicking, since the exporter
+/// must never take the agent down.
+fn nexus_state_to_i32(state: &str) -> i32 {
+    match state {
+        "init" => 0,
+        "open" => 1,
+        "closed" => 2,
+        "faulted" => 3,
+        _ => -1,
+    }
+}
+
+/// Same idea as [`nexus_state_to_i32`] but for the per-child state strings.
+fn child_state_to_i32(state: &str) -> i32 {
+    match state {
+        "init" => 0,
+        "open" => 1,
+        "faulted" => 2,
+        "closed" => 3,
+        _ => -1,
+    }
+}
+
+/// Escape a label value per the Prometheus text exposition format.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Append one gauge sample line, preceded by a `# TYPE` line the first time
+/// `name` is seen.
+fn push_metric(
+    out: &mut String,
+    emitted: &mut std::collections::HashSet<&'static str>,
+    name: &'static str,
+    labels: &[(&str, &str)],
+    value: f64,
+) {
+    if emitted.insert(name) {
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+    }
+    let label_str = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect::<Vec<_>>()
+        .join(",");
+    let _ = writeln!(out, "{}{{{}}} {}", name, label_str, value);
+}
+
+/// Render the current nexus/rebuild state scraped from the mayastor backend
+/// as a Prometheus text-exposition document.
+async fn render(socket: PathBuf) -> String {
+    let mut out = String::new();
+    let mut emitted = std::collections::HashSet::new();
+
+    let nexus_list = match jsonrpc_client::call::<_, ListNexusReply>(
+        &socket,
+        "list_nexus",
+        (),
+    )
+    .await
+    {
+        Ok(reply) => reply.nexus_list,
+        Err(err) => {
+            warn!("metrics scrape failed to list nexuses: {}", err);
+            return out;
+        }
+    };
+
+    for nexus in &nexus_list {
+        push_metric(
+            &mut out,
+            &mut emitted,
+            "mayastor_nexus_state",
+            &[
+                ("uuid", &nexus.uuid),
+                ("device_path", &nexus.device_path),
+            ],
+            nexus_state_to_i32(&nexus.state) as f64,
+        );
+        push_metric(
+            &mut out,
+            &mut emitted,
+            "mayastor_nexus_children_total",
+            &[("uuid", &nexus.uuid)],
+            nexus.children.len() as f64,
+        );
+        push_metric(
+            &mut out,
+            &mut emitted,
+            "mayastor_nexus_rebuilds_active",
+            &[("uuid", &nexus.uuid)],
+            nexus.rebuilds as f64,
+        );
+
+        for child in &nexus.children {
+            push_metric(
+                &mut out,
+                &mut emitted,
+                "mayastor_child_state",
+                &[("uuid", &nexus.uuid), ("child_uri", &child.uri)],
+                child_state_to_i32(&child.state) as f64,
+            );
+        }
+
+        if nexus.rebuilds == 0 {
+            continue;
+        }
+
+        // `get_rebuild_progress` reports progress for the nexus as a whole,
+        // not for a single child, so this is scoped to `uuid` only - do not
+        // attach a `child_uri` label here, there is no per-child value to
+        // report even when several children are rebuilding at once.
+        let progress: u32 = jsonrpc_client::call(
+            &socket,
+            "get_rebuild_progress",
+            RebuildProgressRequest {
+                uuid: nexus.uuid.clone(),
+            },
+        )
+        .await
+        .unwrap_or(0);
+
+        push_metric(
+            &mut out,
+            &mut emitted,
+            "mayastor_rebuild_progress_ratio",
+            &[("uuid", &nexus.uuid)],
+            f64::from(progress) / 100.0,
+        );
+    }
+
+    out
+}
+
+async fn handle(
+    req: Request<Body>,
+    socket: PathBuf,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::GET || req.uri().path() != "/metrics" {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::from("not found"))
+            .unwrap());
+    }
+
+    let body = render(socket).await;
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "text/plain; version=0.0.4")
+        .body(Body::from(body))
+        .unwrap())
+}
+
+/// Serve `/metrics` on `addr` until the process exits, scraping nexus and
+/// rebuild state from the mayastor backend reachable at `ms_socket` on each
+/// request.
+pub async fn serve(addr: SocketAddr, ms_socket: PathBuf) {
+    let make_svc = make_service_fn(move |_conn| {
+        let socket = ms_socket.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle(req, socket.clone())
+            }))
+        }
+    });
+
+    info!("Metrics exporter listening on {}", addr);
+    if let Err(err) = Server::bind(&addr).serve(make_svc).await {
+        error!("Metrics exporter failed: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_backslash_quote_and_newline() {
+        assert_eq!(escape_label("plain"), "plain");
+        assert_eq!(
+            escape_label("nvmf://host/nqn?a=1\\b"),
+            "nvmf://host/nqn?a=1\\\\b"
+        );
+        assert_eq!(escape_label("say \"hi\""), "say \\\"hi\\\"");
+        assert_eq!(escape_label("line1\nline2"), "line1\\nline2");
+    }
+
+    #[test]
+    fn nexus_and_child_state_map_unknown_to_negative_one() {
+        assert_eq!(nexus_state_to_i32("open"), 1);
+        assert_eq!(nexus_state_to_i32("bogus"), -1);
+        assert_eq!(child_state_to_i32("faulted"), 2);
+        assert_eq!(child_state_to_i32("bogus"), -1);
+    }
+}
@@ -0,0 +1,216 @@
+//! CSI `Node` service implementation.
+//!
+//! `NodeStageVolume`/`NodePublishVolume` are where the "exception" mentioned
+//! in the module-level comment in `server.rs` lives: mayastor hands back a
+//! block device (via the same `publish_nexus` JSON-RPC method the egress
+//! gRPC service exposes), and it's this proxy, not mayastor, that mounts it.
+//! The device is not formatted here: it is mounted with the first detected
+//! filesystem type (or ext4). Every RPC that needs the backend goes through
+//! the shared [`Supervisor`] first so a disconnected backend fails fast with
+//! `Status::unavailable` rather than hanging the CO's call.
+
+use std::sync::Arc;
+
+use rpc::mayastor::{PublishNexusRequest, ShareProtocolNexus, UnpublishNexusRequest};
+use tonic::{Request, Response, Status};
+
+use crate::{
+    csi::{
+        node_server::Node as NodeTrait,
+        node_service_capability::{rpc::Type as RpcCapabilityType, Rpc, Type as CapabilityType},
+        NodeExpandVolumeRequest,
+        NodeExpandVolumeResponse,
+        NodeGetCapabilitiesRequest,
+        NodeGetCapabilitiesResponse,
+        NodeGetInfoRequest,
+        NodeGetInfoResponse,
+        NodeGetVolumeStatsRequest,
+        NodeGetVolumeStatsResponse,
+        NodePublishVolumeRequest,
+        NodePublishVolumeResponse,
+        NodeServiceCapability,
+        NodeStageVolumeRequest,
+        NodeStageVolumeResponse,
+        NodeUnpublishVolumeRequest,
+        NodeUnpublishVolumeResponse,
+        NodeUnstageVolumeRequest,
+        NodeUnstageVolumeResponse,
+    },
+    jsonrpc_client,
+    supervisor::Supervisor,
+};
+
+#[derive(Debug)]
+pub struct Node {
+    pub node_name: String,
+    pub addr: String,
+    pub port: u16,
+    pub supervisor: Arc<Supervisor>,
+    /// Filesystem types this node can format volumes with, as detected by
+    /// `mount::probe_filesystems` at startup.
+    pub filesystems: Vec<String>,
+}
+
+impl Node {
+    fn ensure_connected(&self) -> Result<(), Status> {
+        self.supervisor
+            .ensure_connected()
+            .map_err(|err| Status::unavailable(err.to_string()))
+    }
+
+    fn mount(device: &str, target: &str, fstype: &str) -> Result<(), Status> {
+        let (code, _, stderr) = run_script::run_script!(format!(
+            "mkdir -p {target} && mount -t {fstype} {device} {target}",
+            target = target,
+            fstype = fstype,
+            device = device,
+        ))
+        .map_err(|err| Status::internal(format!("failed to run mount: {}", err)))?;
+        if code != 0 {
+            return Err(Status::internal(format!(
+                "mount {} at {} failed: {}",
+                device, target, stderr
+            )));
+        }
+        Ok(())
+    }
+
+    fn unmount(target: &str) -> Result<(), Status> {
+        let (code, _, stderr) =
+            run_script::run_script!(format!("umount {}", target))
+                .map_err(|err| Status::internal(format!("failed to run umount: {}", err)))?;
+        if code != 0 {
+            return Err(Status::internal(format!(
+                "umount {} failed: {}",
+                target, stderr
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl NodeTrait for Node {
+    async fn node_stage_volume(
+        &self,
+        request: Request<NodeStageVolumeRequest>,
+    ) -> Result<Response<NodeStageVolumeResponse>, Status> {
+        self.ensure_connected()?;
+        let args = request.into_inner();
+
+        let reply = jsonrpc_client::call::<_, rpc::mayastor::PublishNexusReply>(
+            self.supervisor.socket(),
+            "publish_nexus",
+            PublishNexusRequest {
+                uuid: args.volume_id.clone(),
+                key: String::new(),
+                share: ShareProtocolNexus::NexusNvmf as i32,
+            },
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
+
+        let fstype = self
+            .filesystems
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "ext4".to_string());
+        Self::mount(&reply.device_path, &args.staging_target_path, &fstype)?;
+
+        Ok(Response::new(NodeStageVolumeResponse {}))
+    }
+
+    async fn node_unstage_volume(
+        &self,
+        request: Request<NodeUnstageVolumeRequest>,
+    ) -> Result<Response<NodeUnstageVolumeResponse>, Status> {
+        self.ensure_connected()?;
+        let args = request.into_inner();
+
+        Self::unmount(&args.staging_target_path)?;
+
+        jsonrpc_client::call::<_, ()>(
+            self.supervisor.socket(),
+            "unpublish_nexus",
+            UnpublishNexusRequest {
+                uuid: args.volume_id,
+            },
+        )
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(NodeUnstageVolumeResponse {}))
+    }
+
+    async fn node_publish_volume(
+        &self,
+        request: Request<NodePublishVolumeRequest>,
+    ) -> Result<Response<NodePublishVolumeResponse>, Status> {
+        self.ensure_connected()?;
+        let args = request.into_inner();
+
+        let (code, _, stderr) = run_script::run_script!(format!(
+            "mkdir -p {target} && mount --bind {source} {target}",
+            target = args.target_path,
+            source = args.staging_target_path,
+        ))
+        .map_err(|err| Status::internal(format!("failed to run mount: {}", err)))?;
+        if code != 0 {
+            return Err(Status::internal(format!(
+                "bind mount {} at {} failed: {}",
+                args.staging_target_path, args.target_path, stderr
+            )));
+        }
+
+        Ok(Response::new(NodePublishVolumeResponse {}))
+    }
+
+    async fn node_unpublish_volume(
+        &self,
+        request: Request<NodeUnpublishVolumeRequest>,
+    ) -> Result<Response<NodeUnpublishVolumeResponse>, Status> {
+        let args = request.into_inner();
+        Self::unmount(&args.target_path)?;
+        Ok(Response::new(NodeUnpublishVolumeResponse {}))
+    }
+
+    async fn node_get_volume_stats(
+        &self,
+        _request: Request<NodeGetVolumeStatsRequest>,
+    ) -> Result<Response<NodeGetVolumeStatsResponse>, Status> {
+        Err(Status::unimplemented(
+            "node_get_volume_stats is not supported",
+        ))
+    }
+
+    async fn node_expand_volume(
+        &self,
+        _request: Request<NodeExpandVolumeRequest>,
+    ) -> Result<Response<NodeExpandVolumeResponse>, Status> {
+        Err(Status::unimplemented("node_expand_volume is not supported"))
+    }
+
+    async fn node_get_capabilities(
+        &self,
+        _request: Request<NodeGetCapabilitiesRequest>,
+    ) -> Result<Response<NodeGetCapabilitiesResponse>, Status> {
+        Ok(Response::new(NodeGetCapabilitiesResponse {
+            capabilities: vec![NodeServiceCapability {
+                r#type: Some(CapabilityType::Rpc(Rpc {
+                    r#type: RpcCapabilityType::StageUnstageVolume as i32,
+                })),
+            }],
+        }))
+    }
+
+    async fn node_get_info(
+        &self,
+        _request: Request<NodeGetInfoRequest>,
+    ) -> Result<Response<NodeGetInfoResponse>, Status> {
+        Ok(Response::new(NodeGetInfoResponse {
+            node_id: self.node_name.clone(),
+            max_volumes_per_node: 0,
+            accessible_topology: None,
+        }))
+    }
+}
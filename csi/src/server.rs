@@ -29,17 +29,25 @@ use std::{
     task::{Context, Poll},
 };
 use tokio::{net::UnixListener, prelude::*};
-use tonic::transport::{server::Connected, Server};
+use tonic::transport::{
+    server::Connected,
+    Certificate,
+    Identity as TlsIdentity,
+    Server,
+    ServerTlsConfig,
+};
 
 use git_version::git_version;
 // These libs are needed for gRPC generated code
 use rpc::{self, service::mayastor_server::MayastorServer};
+use tonic_health::server::health_reporter;
 
 use crate::{
     identity::Identity,
     mayastor_svc::MayastorService,
     mount::probe_filesystems,
     node::Node,
+    supervisor::Supervisor,
 };
 
 #[allow(dead_code)]
@@ -51,11 +59,16 @@ pub mod csi {
     tonic::include_proto!("csi.v1");
 }
 
+mod admin;
 mod format;
+mod health;
 mod identity;
+mod jsonrpc_client;
 mod mayastor_svc;
+mod metrics;
 mod mount;
 mod node;
+mod supervisor;
 
 #[derive(Debug)]
 struct UnixStream(tokio::net::UnixStream);
@@ -96,6 +109,51 @@ impl AsyncWrite for UnixStream {
     }
 }
 
+/// Build the TLS config for the egress gRPC endpoint from the `--tls-*`
+/// args, if any were given. Returns `Ok(None)` when none of `--tls-cert`,
+/// `--tls-key` and `--tls-ca` was specified, so the endpoint stays plaintext;
+/// `--tls-ca` without a cert and key is an error.
+fn load_tls_config(
+    tls_cert: Option<&str>,
+    tls_key: Option<&str>,
+    tls_ca: Option<&str>,
+) -> Result<Option<ServerTlsConfig>, String> {
+    let (cert_path, key_path) = match (tls_cert, tls_key) {
+        (Some(cert), Some(key)) => (cert, key),
+        (None, None) => {
+            if tls_ca.is_some() {
+                return Err(
+                    "--tls-ca requires --tls-cert and --tls-key to also be specified"
+                        .to_string(),
+                );
+            }
+            return Ok(None);
+        }
+        _ => {
+            return Err(
+                "--tls-cert and --tls-key must be specified together"
+                    .to_string(),
+            )
+        }
+    };
+
+    let cert = fs::read(cert_path)
+        .map_err(|err| format!("Error reading {}: {}", cert_path, err))?;
+    let key = fs::read(key_path)
+        .map_err(|err| format!("Error reading {}: {}", key_path, err))?;
+
+    let mut config =
+        ServerTlsConfig::new().identity(TlsIdentity::from_pem(cert, key));
+
+    if let Some(ca_path) = tls_ca {
+        let ca = fs::read(ca_path)
+            .map_err(|err| format!("Error reading {}: {}", ca_path, err))?;
+        config = config.client_ca_root(Certificate::from_pem(ca));
+    }
+
+    Ok(Some(config))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), String> {
     let matches = App::new("Mayastor agent")
@@ -134,6 +192,41 @@ async fn main() -> Result<(), String> {
                 .help("CSI gRPC listen socket (default /var/tmp/csi.sock)")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("metrics-port")
+                .long("metrics-port")
+                .value_name("NUMBER")
+                .help("Port to expose a Prometheus /metrics endpoint on (default: disabled)")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("admin-port")
+                .long("admin-port")
+                .value_name("NUMBER")
+                .help("Port to expose a REST admin API on (default: disabled)")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls-cert")
+                .long("tls-cert")
+                .value_name("PATH")
+                .help("PEM file with certificate for the egress gRPC endpoint (default: plaintext)")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls-key")
+                .long("tls-key")
+                .value_name("PATH")
+                .help("PEM file with private key matching --tls-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls-ca")
+                .long("tls-ca")
+                .value_name("PATH")
+                .help("PEM file with CA used to verify client certs on the egress gRPC endpoint (enables mTLS)")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("log-debug")
                 .short("l")
@@ -165,6 +258,14 @@ async fn main() -> Result<(), String> {
     let csi_socket = matches
         .value_of("csi-socket")
         .unwrap_or("/var/tmp/csi.sock");
+    let metrics_port = match matches.value_of("metrics-port") {
+        Some(_) => Some(value_t!(matches, "metrics-port", u16).unwrap_or_else(|e| e.exit())),
+        None => None,
+    };
+    let admin_port = match matches.value_of("admin-port") {
+        Some(_) => Some(value_t!(matches, "admin-port", u16).unwrap_or_else(|e| e.exit())),
+        None => None,
+    };
     let level = match matches.occurrences_of("v") as usize {
         0 => "info",
         1 => "debug",
@@ -199,10 +300,42 @@ async fn main() -> Result<(), String> {
     let saddr = format!("{}:{}", addr, port).parse().unwrap();
     info!("Agent starting service on {}", saddr);
 
-    let tcp = Server::builder()
+    // Owns the connection to the mayastor backend: probes it on a timer and
+    // lets the gRPC services fail fast instead of hanging on a dead socket.
+    let supervisor = Supervisor::spawn(ms_socket.into());
+
+    let (mut tcp_health_reporter, tcp_health_service) = health_reporter();
+    let (mut uds_health_reporter, uds_health_service) = health_reporter();
+    tcp_health_reporter
+        .set_not_serving::<MayastorServer<MayastorService>>()
+        .await;
+    uds_health_reporter.set_not_serving::<NodeServer<Node>>().await;
+
+    let tls_config = load_tls_config(
+        matches.value_of("tls-cert"),
+        matches.value_of("tls-key"),
+        matches.value_of("tls-ca"),
+    )?;
+
+    let mut tcp_builder = Server::builder();
+    if let Some(tls_config) = tls_config {
+        info!(
+            "TLS enabled on egress gRPC endpoint{}",
+            if matches.value_of("tls-ca").is_some() {
+                " (client certificates required)"
+            } else {
+                ""
+            }
+        );
+        tcp_builder = tcp_builder
+            .tls_config(tls_config)
+            .map_err(|err| format!("Error configuring TLS: {}", err))?;
+    }
+    let tcp = tcp_builder
         .add_service(MayastorServer::new(MayastorService {
-            socket: ms_socket.into(),
+            supervisor: supervisor.clone(),
         }))
+        .add_service(tcp_health_service)
         .serve(saddr);
 
     // Remove stale CSI socket from previous instance if there is any
@@ -226,13 +359,72 @@ async fn main() -> Result<(), String> {
             node_name: node_name.into(),
             addr: addr.to_string(),
             port,
-            socket: ms_socket.into(),
+            supervisor: supervisor.clone(),
             filesystems: probe_filesystems().unwrap(),
         }))
         .add_service(IdentityServer::new(Identity {
-            socket: ms_socket.into(),
+            supervisor: supervisor.clone(),
         }))
+        .add_service(uds_health_service)
         .serve_with_incoming(uds_sock.incoming().map_ok(UnixStream));
-    let _ = futures::future::join(uds, tcp).await;
+
+    let metrics: Pin<Box<dyn std::future::Future<Output = ()>>> =
+        match metrics_port {
+            Some(metrics_port) => {
+                let metrics_addr =
+                    format!("{}:{}", addr, metrics_port).parse().unwrap();
+                Box::pin(metrics::serve(metrics_addr, ms_socket.into()))
+            }
+            None => Box::pin(futures::future::pending()),
+        };
+
+    let admin: Pin<Box<dyn std::future::Future<Output = ()>>> =
+        match admin_port {
+            Some(admin_port) => {
+                let admin_addr =
+                    format!("{}:{}", addr, admin_port).parse().unwrap();
+                Box::pin(admin::serve(admin_addr, supervisor.clone()))
+            }
+            None => Box::pin(futures::future::pending()),
+        };
+
+    let tcp_health_watch =
+        health::watch::<MayastorServer<MayastorService>>(
+            supervisor.clone(),
+            tcp_health_reporter,
+        );
+    let uds_health_watch =
+        health::watch::<NodeServer<Node>>(supervisor, uds_health_reporter);
+
+    let _ = futures::future::join(
+        futures::future::join5(uds, tcp, metrics, admin, tcp_health_watch),
+        uds_health_watch,
+    )
+    .await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_tls_args_stays_plaintext() {
+        assert!(load_tls_config(None, None, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn cert_without_key_is_an_error() {
+        assert!(load_tls_config(Some("cert.pem"), None, None).is_err());
+    }
+
+    #[test]
+    fn key_without_cert_is_an_error() {
+        assert!(load_tls_config(None, Some("key.pem"), None).is_err());
+    }
+
+    #[test]
+    fn ca_without_cert_and_key_is_an_error() {
+        assert!(load_tls_config(None, None, Some("ca.pem")).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Supervises the connection to the mayastor JSON-RPC backend.
+//!
+//! Every gRPC service in this crate used to dial `ms_socket` directly, once
+//! per request, with no way to tell a slow backend from a dead one. The
+//! `Supervisor` instead owns the connection state: it probes the backend on
+//! a timer, flips to [`ConnectionState::Disconnected`] the moment a probe
+//! fails, and retries with capped exponential backoff until the backend
+//! comes back. Services hold a shared handle to it and can fail fast
+//! instead of hanging on a socket that isn't coming back any time soon.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::time::delay_for;
+
+use crate::jsonrpc_client;
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    Disconnected,
+}
+
+/// Error returned by services that need a live backend connection but find
+/// the supervisor in [`ConnectionState::Disconnected`].
+#[derive(Debug, Clone)]
+pub struct BackendUnavailable;
+
+impl std::fmt::Display for BackendUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "mayastor backend is currently unavailable")
+    }
+}
+
+impl std::error::Error for BackendUnavailable {}
+
+#[derive(Debug)]
+pub struct Supervisor {
+    socket: PathBuf,
+    connected: AtomicBool,
+}
+
+impl Supervisor {
+    /// Create a supervisor for `socket` and spawn its background probe
+    /// loop. The returned handle starts out `Disconnected` until the first
+    /// successful probe.
+    pub fn spawn(socket: PathBuf) -> Arc<Self> {
+        let supervisor = Arc::new(Self {
+            socket,
+            connected: AtomicBool::new(false),
+        });
+
+        let task_handle = supervisor.clone();
+        tokio::spawn(async move { task_handle.run().await });
+
+        supervisor
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        if self.connected.load(Ordering::Relaxed) {
+            ConnectionState::Connected
+        } else {
+            ConnectionState::Disconnected
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.state() == ConnectionState::Connected
+    }
+
+    /// Fail fast with [`BackendUnavailable`] instead of letting a caller
+    /// dial a backend the supervisor already knows is down.
+    pub fn ensure_connected(&self) -> Result<(), BackendUnavailable> {
+        if self.is_connected() {
+            Ok(())
+        } else {
+            Err(BackendUnavailable)
+        }
+    }
+
+    /// A single lightweight liveness probe against the mayastor socket.
+    async fn probe(&self) -> bool {
+        jsonrpc_client::call::<_, rpc::mayastor::ListNexusReply>(
+            &self.socket,
+            "list_nexus",
+            (),
+        )
+        .await
+        .is_ok()
+    }
+
+    fn set_connected(&self, connected: bool) {
+        if self.connected.swap(connected, Ordering::Relaxed) != connected {
+            if connected {
+                info!("mayastor backend at {:?} is reachable again", self.socket);
+            } else {
+                warn!("mayastor backend at {:?} became unreachable", self.socket);
+            }
+        }
+    }
+
+    async fn run(self: Arc<Self>) {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            if self.probe().await {
+                self.set_connected(true);
+                backoff = INITIAL_BACKOFF;
+                delay_for(PROBE_INTERVAL).await;
+            } else {
+                self.set_connected(false);
+                delay_for(backoff).await;
+                backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use futures::{future, FutureExt};
+use std::time::Duration;
+
+use futures::{future, stream, stream::Stream, stream::StreamExt, FutureExt};
+use serde::Serialize;
 use uuid::Uuid;
 
 use rpc::mayastor::{
@@ -66,6 +69,111 @@ fn name_to_uuid(name: &str) -> &str {
     }
 }
 
+/// How often [`watch_rebuild`] re-checks rebuild state/progress while the
+/// rebuild is still running.
+const WATCH_REBUILD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// TODO: get rid of this hardcoded nexus block size once it can be derived
+// from the child bdevs, same as the one in `create_nexus` below.
+const NEXUS_BLOCK_SIZE: u64 = 512;
+
+/// One progress/state sample emitted by [`watch_rebuild`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RebuildProgressUpdate {
+    pub state: String,
+    pub progress_ratio: f64,
+    pub blocks_done: u64,
+    pub blocks_total: u64,
+}
+
+/// Error surfaced by the [`watch_rebuild`] stream: either the usual nexus
+/// lookup error, or a dedicated terminal variant for a rebuild that ended
+/// in the "failed" state, so callers can match on `Err` instead of string
+/// comparing `state`.
+#[derive(Debug)]
+pub enum RebuildWatchError {
+    Lookup(Error),
+    Failed { uri: String },
+}
+
+impl From<Error> for RebuildWatchError {
+    fn from(err: Error) -> Self {
+        RebuildWatchError::Lookup(err)
+    }
+}
+
+impl std::fmt::Display for RebuildWatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RebuildWatchError::Lookup(err) => write!(f, "{}", err),
+            RebuildWatchError::Failed {
+                uri,
+            } => write!(f, "rebuild of child {} failed", uri),
+        }
+    }
+}
+
+/// Stream progress/state updates for the rebuild of `uri` on the nexus
+/// identified by `uuid` until it reaches a terminal state. Registered below
+/// as the `watch_rebuild` JSON-RPC method, which drains the stream
+/// server-side and replies once with the full history, since the JSON-RPC
+/// transport here is one request to one reply rather than a push stream;
+/// `pub` (not `pub(crate)`) so it is also reachable directly by callers
+/// embedded in the same process, such as `nexus_rebuild.rs`'s test. The
+/// `get_rebuild_state` and `get_rebuild_progress` JSON-RPC methods it samples
+/// remain available unchanged for callers that still want to poll instead.
+pub fn watch_rebuild(
+    uuid: &str,
+    uri: &str,
+) -> Result<impl Stream<Item = Result<RebuildProgressUpdate, RebuildWatchError>>, Error>
+{
+    // Fail immediately on an unknown uuid rather than on the first stream
+    // poll.
+    nexus_lookup(uuid)?;
+
+    let uuid = uuid.to_owned();
+    let uri = uri.to_owned();
+
+    Ok(stream::unfold(Some((uuid, uri, false)), |cursor| async move {
+        let (uuid, uri, should_delay) = cursor?;
+        if should_delay {
+            tokio::time::delay_for(WATCH_REBUILD_POLL_INTERVAL).await;
+        }
+
+        let sample = async {
+            let nexus = nexus_lookup(&uuid)?;
+            let state = nexus.get_rebuild_state(&uri).await?;
+            if state == "failed" {
+                return Err(RebuildWatchError::Failed {
+                    uri: uri.clone(),
+                });
+            }
+
+            // A transient failure to read progress shouldn't end the watch
+            // early; the rebuild state above is the source of truth for
+            // whether this update is terminal.
+            let progress = nexus.get_rebuild_progress().await.unwrap_or(0);
+            let blocks_total = nexus.size() / NEXUS_BLOCK_SIZE;
+            let blocks_done =
+                (blocks_total as f64 * f64::from(progress) / 100.0) as u64;
+            Ok(RebuildProgressUpdate {
+                state,
+                progress_ratio: f64::from(progress) / 100.0,
+                blocks_done,
+                blocks_total,
+            })
+        }
+        .await;
+
+        let next_cursor = match &sample {
+            Ok(update) if update.state == "running" => Some((uuid, uri, true)),
+            _ => None,
+        };
+
+        Some((sample, next_cursor))
+    }))
+}
+
 pub(crate) fn register_rpc_methods() {
     // JSON rpc method to list the nexus and their states
     jsonrpc_register::<(), _, _, Error>("list_nexus", |_| {
@@ -227,4 +335,24 @@ pub(crate) fn register_rpc_methods() {
         };
         fut.boxed_local()
     });
+
+    // Drains `watch_rebuild`'s stream server-side and replies once with the
+    // full progress/state history, since this JSON-RPC transport is one
+    // request to one reply rather than a push stream.
+    jsonrpc_register::<_, _, _, RebuildWatchError>(
+        "watch_rebuild",
+        |args: RebuildStateRequest| {
+            let fut = async move {
+                let stream = watch_rebuild(&args.uuid, &args.uri)
+                    .map_err(RebuildWatchError::Lookup)?;
+                futures::pin_mut!(stream);
+                let mut updates = Vec::new();
+                while let Some(sample) = stream.next().await {
+                    updates.push(sample?);
+                }
+                Ok(updates)
+            };
+            fut.boxed_local()
+        },
+    );
 }
@@ -1,11 +1,16 @@
 use crossbeam::channel::{after, select, unbounded};
+use futures::StreamExt;
 use log::info;
 use std::time::Duration;
 
 pub mod common;
 
 use mayastor::{
-    bdev::{nexus_create, nexus_lookup},
+    bdev::{
+        nexus::nexus_rpc::watch_rebuild,
+        nexus_create,
+        nexus_lookup,
+    },
     core::{mayastor_env_stop, MayastorCliArgs, MayastorEnvironment, Reactor},
 };
 
@@ -97,3 +102,63 @@ async fn create_nexus() {
         .await
         .unwrap();
 }
+
+static DISKNAME3: &str = "/tmp/disk3.img";
+static BDEVNAME3: &str = "aio:///tmp/disk3.img?blk_size=512";
+
+static DISKNAME4: &str = "/tmp/disk4.img";
+static BDEVNAME4: &str = "aio:///tmp/disk4.img?blk_size=512";
+
+static WATCH_NEXUS_UUID: &str = "9f46dc16-2da9-4a26-a189-6e1d4a25a2f9";
+
+#[test]
+fn watch_rebuild_test() {
+    common::delete_file(&[DISKNAME3.into(), DISKNAME4.into()]);
+    common::truncate_file(DISKNAME3, NEXUS_SIZE / 1024);
+    common::truncate_file(DISKNAME4, NEXUS_SIZE / 1024);
+
+    test_init!();
+
+    Reactor::block_on(watch_rebuild_test_start());
+
+    common::delete_file(&[DISKNAME3.into(), DISKNAME4.into()]);
+}
+
+// End-to-end exercise of `nexus_rpc::watch_rebuild`: drives a real rebuild
+// to completion and asserts the stream terminates with a non-"running"
+// state and a fully caught-up block count, instead of just unit-testing its
+// internal cursor logic.
+async fn watch_rebuild_test_start() {
+    let name = format!("nexus-{}", WATCH_NEXUS_UUID);
+    nexus_create(
+        &name,
+        NEXUS_SIZE,
+        Some(WATCH_NEXUS_UUID),
+        &[BDEVNAME3.to_string()],
+    )
+    .await
+    .unwrap();
+
+    let nexus = nexus_lookup(&name).unwrap();
+    nexus
+        .share(ShareProtocolNexus::NexusNbd, None)
+        .await
+        .unwrap();
+    nexus.add_child(BDEVNAME4).await.unwrap();
+    nexus.start_rebuild_rpc(BDEVNAME4).await.unwrap();
+
+    let stream = watch_rebuild(WATCH_NEXUS_UUID, BDEVNAME4).unwrap();
+    futures::pin_mut!(stream);
+
+    let mut updates = Vec::new();
+    while let Some(sample) = stream.next().await {
+        updates.push(sample.expect("rebuild watch stream reported an error"));
+    }
+
+    assert!(!updates.is_empty(), "expected at least one progress update");
+    let last = updates.last().unwrap();
+    assert_ne!(last.state, "running");
+    assert_eq!(last.blocks_done, last.blocks_total);
+
+    mayastor_env_stop(0);
+}